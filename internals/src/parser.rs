@@ -24,24 +24,48 @@ pub struct Item {
     pub url_large: Option<String>,
 }
 
+pub struct ParseWarning {
+    pub start: usize,
+    pub end: usize,
+    pub reason: String,
+}
+
+enum ParseMode {
+    Strict,
+    Lenient(SyncSender<ParseWarning>),
+}
+
 pub struct Parser {
     sender: SyncSender<Item>,
     decoder: XzDecoder<Vec<u8>>,
     pos: usize,
+    consumed: usize,
     parsed_header: bool,
+    mode: ParseMode,
 }
 
 impl Parser {
     pub fn new(sender: SyncSender<Item>) -> Self {
+        Self::with_mode(sender, ParseMode::Strict)
+    }
+
+    pub fn new_lenient(sender: SyncSender<Item>, warnings: SyncSender<ParseWarning>) -> Self {
+        Self::with_mode(sender, ParseMode::Lenient(warnings))
+    }
+
+    fn with_mode(sender: SyncSender<Item>, mode: ParseMode) -> Self {
         Self {
             sender,
             decoder: XzDecoder::new(Vec::new()),
             pos: 0,
+            consumed: 0,
             parsed_header: false,
+            mode,
         }
     }
 
     pub fn parse(&mut self, data: &[u8]) -> Fallible {
+        self.consumed += self.pos;
         shift_data(self.decoder.get_mut(), &mut self.pos);
         self.decoder.write_all(data)?;
 
@@ -58,12 +82,16 @@ impl Parser {
         }
 
         loop {
-            match parse_item(&buf[self.pos..])? {
-                Some((parsed, item)) => {
+            match parse_item(&buf[self.pos..]) {
+                Ok(Some((parsed, item))) => {
                     self.pos += parsed;
                     self.sender.send(item)?;
                 }
-                None => return Ok(()),
+                Ok(None) => return Ok(()),
+                Err(err) => match self.resync(buf, err)? {
+                    Some(resumed) => self.pos = resumed,
+                    None => return Ok(()),
+                },
             }
         }
     }
@@ -71,16 +99,65 @@ impl Parser {
     pub fn finish(mut self) -> Fallible {
         let buf = self.decoder.finish()?;
 
-        while let Some((parsed, item)) = parse_item(&buf[self.pos..])? {
-            self.pos += parsed;
-            self.sender.send(item)?;
+        loop {
+            match parse_item(&buf[self.pos..]) {
+                Ok(Some((parsed, item))) => {
+                    self.pos += parsed;
+                    self.sender.send(item)?;
+                }
+                Ok(None) => break,
+                Err(err) => match self.resync(&buf, err)? {
+                    Some(resumed) => self.pos = resumed,
+                    None => break,
+                },
+            }
         }
 
-        let item = parse_last_item(&buf[self.pos..])?;
-        self.sender.send(item)?;
+        match parse_last_item(&buf[self.pos..]) {
+            Ok(item) => self.sender.send(item)?,
+            Err(err) => match self.warnings() {
+                Some(warnings) => {
+                    let _ = warnings.send(ParseWarning {
+                        start: self.consumed + self.pos,
+                        end: self.consumed + buf.len(),
+                        reason: err.to_string(),
+                    });
+                }
+                None => return Err(err),
+            },
+        }
 
         Ok(())
     }
+
+    fn warnings(&self) -> Option<&SyncSender<ParseWarning>> {
+        match &self.mode {
+            ParseMode::Strict => None,
+            ParseMode::Lenient(warnings) => Some(warnings),
+        }
+    }
+
+    fn resync(&self, buf: &[u8], err: Error) -> Fallible<Option<usize>> {
+        let warnings = match self.warnings() {
+            Some(warnings) => warnings,
+            None => return Err(err),
+        };
+
+        match find_next_item(&buf[self.pos..]) {
+            Some(offset) => {
+                let resumed = self.pos + offset;
+
+                let _ = warnings.send(ParseWarning {
+                    start: self.consumed + self.pos,
+                    end: self.consumed + resumed,
+                    reason: err.to_string(),
+                });
+
+                Ok(Some(resumed))
+            }
+            None => Ok(None),
+        }
+    }
 }
 
 fn shift_data(buf: &mut Vec<u8>, pos: &mut usize) {
@@ -136,6 +213,12 @@ fn parse_item(input: &[u8]) -> Fallible<Option<(usize, Item)>> {
     Ok(Some((PREFIX.len() + pos + 2, item)))
 }
 
+fn find_next_item(input: &[u8]) -> Option<usize> {
+    const SUFFIX: &[u8] = b"],\"X\":[";
+
+    find_bytes(input, SUFFIX).map(|pos| pos + 2)
+}
+
 fn parse_last_item(input: &[u8]) -> Fallible<Item> {
     const PREFIX: &[u8] = b"\"X\":[";
     const SUFFIX: &[u8] = b"]}";
@@ -270,8 +353,53 @@ fn parse_url_suffix(url: &str, mut field: String) -> Fallible<Option<String>> {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::mpsc::sync_channel;
+
+    use xz2::write::XzEncoder;
+
     use super::*;
 
+    fn sample_fields(channel: &str) -> String {
+        let fields = [
+            channel,
+            "topic",
+            "title",
+            "01.01.2020",
+            "00:00:00",
+            "00:00:00",
+            "",
+            "desc",
+            "url",
+            "site",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+        ];
+
+        fields
+            .iter()
+            .map(|field| format!("{:?}", field))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    fn compress(document: &str) -> Vec<u8> {
+        let mut compressed = Vec::new();
+
+        let mut encoder = XzEncoder::new(&mut compressed, 6);
+        encoder.write_all(document.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+
+        compressed
+    }
+
     #[test]
     fn url_suffix() {
         assert_eq!(None, parse_url_suffix("foo://bar", "".to_owned()).unwrap());
@@ -286,4 +414,98 @@ mod tests {
             parse_url_suffix("foo://bar/baz", "10|qux".to_owned()).unwrap()
         );
     }
+
+    #[test]
+    fn next_item() {
+        assert_eq!(None, find_next_item(b"\"X\":[1,2"));
+
+        let input = b"garbage],\"X\":[\"a\"]";
+        let offset = find_next_item(input).unwrap();
+
+        assert_eq!(9, offset);
+        assert_eq!(b"\"X\":[\"a\"]".as_ref(), &input[offset..]);
+
+        let input = b"],\"X\":[\"a\"]";
+        let offset = find_next_item(input).unwrap();
+
+        assert_eq!(2, offset);
+        assert_eq!(b"\"X\":[\"a\"]".as_ref(), &input[offset..]);
+    }
+
+    #[test]
+    fn lenient_skips_corrupt_item() {
+        let document = format!(
+            "{{\"Filmliste\":[],\"X\":[this is not a valid item],\"X\":[{}]}}",
+            sample_fields("chan-2")
+        );
+
+        let (item_tx, item_rx) = sync_channel(8);
+        let (warn_tx, warn_rx) = sync_channel(8);
+
+        let mut parser = Parser::new_lenient(item_tx, warn_tx);
+        parser.parse(&compress(&document)).unwrap();
+        parser.finish().unwrap();
+
+        let item = item_rx.recv().unwrap();
+        assert_eq!("chan-2", item.channel);
+        assert!(item_rx.try_recv().is_err());
+
+        let warning = warn_rx.recv().unwrap();
+        assert!(warning.end > warning.start);
+    }
+
+    #[test]
+    fn lenient_warning_offsets_survive_chunked_feeding() {
+        let header = "{\"Filmliste\":[],\"X\":[";
+        let corrupt = "this is not a valid item";
+
+        let document = format!(
+            "{}{}],\"X\":[{}],\"X\":[{}]}}",
+            header,
+            sample_fields("chan-1"),
+            corrupt,
+            sample_fields("chan-last")
+        );
+        let corrupt_start = document.find(corrupt).unwrap() - "\"X\":[".len();
+
+        let (item_tx, item_rx) = sync_channel(8);
+        let (warn_tx, warn_rx) = sync_channel(8);
+
+        let mut parser = Parser::new_lenient(item_tx, warn_tx);
+        for byte in compress(&document) {
+            parser.parse(&[byte]).unwrap();
+        }
+        parser.finish().unwrap();
+
+        let item = item_rx.recv().unwrap();
+        assert_eq!("chan-1", item.channel);
+
+        let item = item_rx.recv().unwrap();
+        assert_eq!("chan-last", item.channel);
+        assert!(item_rx.try_recv().is_err());
+
+        let warning = warn_rx.recv().unwrap();
+        assert_eq!(corrupt_start, warning.start);
+    }
+
+    #[test]
+    fn lenient_warns_on_malformed_last_item() {
+        let document = format!(
+            "{{\"Filmliste\":[],\"X\":[{}],\"X\":[1,2]}}",
+            sample_fields("chan-1")
+        );
+
+        let (item_tx, item_rx) = sync_channel(8);
+        let (warn_tx, warn_rx) = sync_channel(8);
+
+        let mut parser = Parser::new_lenient(item_tx, warn_tx);
+        parser.parse(&compress(&document)).unwrap();
+        parser.finish().unwrap();
+
+        let item = item_rx.recv().unwrap();
+        assert_eq!("chan-1", item.channel);
+        assert!(item_rx.try_recv().is_err());
+
+        warn_rx.recv().unwrap();
+    }
 }